@@ -0,0 +1,58 @@
+use subxt::sp_core::crypto::AccountId32 as AccountId;
+
+use crate::error::SubspaceCliError;
+use crate::output::{self, BalanceHistoryRow, OutputFormat};
+use crate::{Api, BlockNumber};
+use anyhow::{anyhow, Result};
+
+/// Walks every block in `[from, to]` and emits `who`'s free/reserved balance alongside the
+/// total issuance at that block, resolving each block number to its hash along the way.
+pub async fn history(
+    api: &Api,
+    who: AccountId,
+    from: BlockNumber,
+    to: BlockNumber,
+    format: OutputFormat,
+) -> Result<()> {
+    if from > to {
+        return Err(anyhow!(
+            "--from {} is after --to {}; the range must be non-decreasing",
+            from,
+            to,
+        ));
+    }
+
+    let mut rows = Vec::with_capacity(to.saturating_sub(from).saturating_add(1) as usize);
+
+    for number in from..=to {
+        let hash = api
+            .client
+            .rpc()
+            .block_hash(Some(number.into()))
+            .await
+            .map_err(SubspaceCliError::Rpc)?
+            .ok_or(SubspaceCliError::BlockHashNotFound { number })?;
+
+        let account = api
+            .storage()
+            .system()
+            .account(&who, Some(hash))
+            .await
+            .map_err(SubspaceCliError::Rpc)?;
+        let issuance = api
+            .storage()
+            .balances()
+            .total_issuance(Some(hash))
+            .await
+            .map_err(SubspaceCliError::Rpc)?;
+
+        rows.push(BalanceHistoryRow {
+            number,
+            free: account.data.free,
+            reserved: account.data.reserved,
+            issuance,
+        });
+    }
+
+    output::print_rows(&rows, format)
+}
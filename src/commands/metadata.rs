@@ -0,0 +1,91 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use codec::Decode;
+use frame_metadata::RuntimeMetadataPrefixed;
+
+use crate::error::SubspaceCliError;
+use crate::{Api, BlockHash};
+
+/// The `spec_version` the bundled `subspace_metadata.scale` was generated from.
+///
+/// `spec_version` lives in `RuntimeVersion`, not in the metadata bytes themselves, so it can't
+/// be derived from `subspace_metadata.scale` by decoding it - instead it's captured alongside
+/// that file, in `subspace_spec_version.txt`, at the same time the metadata is regenerated, so
+/// the two stay in lockstep instead of drifting apart behind a disconnected magic constant.
+fn baked_in_spec_version() -> u32 {
+    include_str!("../../subspace_spec_version.txt")
+        .trim()
+        .parse()
+        .expect("subspace_spec_version.txt must contain a single integer")
+}
+
+/// Compares the connected node's live `spec_version` against [`baked_in_spec_version`] and
+/// warns on a mismatch rather than refusing outright, since the statically generated `Api`
+/// may still decode calls from unchanged pallets just fine.
+pub async fn check_spec_version(api: &Api, block_hash: BlockHash) -> Result<()> {
+    let runtime_version = api
+        .client
+        .rpc()
+        .runtime_version(Some(block_hash))
+        .await
+        .map_err(SubspaceCliError::Rpc)?;
+    let baked_in = baked_in_spec_version();
+
+    if runtime_version.spec_version != baked_in {
+        eprintln!(
+            "warning: node runtime spec_version {} does not match the spec_version {} the \
+             bundled metadata was generated from; some calls may fail to decode. Regenerate \
+             `subspace_metadata.scale` (and `subspace_spec_version.txt`) and rebuild to clear \
+             this warning.",
+            runtime_version.spec_version, baked_in,
+        );
+    }
+
+    Ok(())
+}
+
+/// Prints the node's live runtime version and, if `path` is given, decodes and prints that
+/// `.scale` file's metadata; without `path` it prints only the bundled metadata's
+/// `spec_version`.
+///
+/// Loading an alternate file only affects this command's own output - the rest of the CLI
+/// still talks to the node through the statically generated `Api`, which stays bound to the
+/// metadata it was compiled against.
+pub async fn metadata(api: &Api, block_hash: BlockHash, path: Option<PathBuf>) -> Result<()> {
+    let runtime_version = api
+        .client
+        .rpc()
+        .runtime_version(Some(block_hash))
+        .await
+        .map_err(SubspaceCliError::Rpc)?;
+    println!("Node runtime version: {:#?}", runtime_version);
+
+    match path {
+        Some(path) => {
+            let bytes = fs::read(&path).map_err(|err| {
+                anyhow!("Failed to read metadata file {}: {}", path.display(), err)
+            })?;
+            let metadata = RuntimeMetadataPrefixed::decode(&mut bytes.as_slice()).map_err(|err| {
+                anyhow!("Failed to decode metadata file {}: {}", path.display(), err)
+            })?;
+            println!("Loaded metadata from {}: {:#?}", path.display(), metadata);
+        }
+        None => {
+            println!("Bundled metadata spec_version: {}", baked_in_spec_version());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn baked_in_spec_version_file_parses_to_a_positive_integer() {
+        assert!(baked_in_spec_version() > 0);
+    }
+}
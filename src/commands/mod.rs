@@ -0,0 +1,4 @@
+pub mod history;
+pub mod metadata;
+pub mod snapshot;
+pub mod verify;
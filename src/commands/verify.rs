@@ -0,0 +1,360 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use codec::{Decode, Encode};
+use subxt::sp_core::hashing::{blake2_128, blake2_256, twox_128, twox_64};
+
+use crate::error::SubspaceCliError;
+use crate::{Api, BlockHash};
+
+/// A proof node keyed by the blake2-256 hash of its SCALE-encoded bytes, as returned by
+/// `state_getReadProof`.
+type ProofDb = HashMap<[u8; 32], Vec<u8>>;
+
+/// Storage key for `System::Account(who)`.
+pub fn system_account_key(who: &subxt::sp_core::crypto::AccountId32) -> Vec<u8> {
+    storage_map_key("System", "Account", &blake2_128_concat(who.as_ref()))
+}
+
+/// Storage key for `System::BlockHash(number)`.
+pub fn system_block_hash_key(number: crate::BlockNumber) -> Vec<u8> {
+    storage_map_key("System", "BlockHash", &twox_64_concat(&number.encode_scale()))
+}
+
+fn storage_map_key(pallet: &str, item: &str, hashed_key: &[u8]) -> Vec<u8> {
+    let mut key = twox_128(pallet.as_bytes()).to_vec();
+    key.extend_from_slice(&twox_128(item.as_bytes()));
+    key.extend_from_slice(hashed_key);
+    key
+}
+
+fn blake2_128_concat(data: &[u8]) -> Vec<u8> {
+    let mut out = blake2_128(data).to_vec();
+    out.extend_from_slice(data);
+    out
+}
+
+fn twox_64_concat(data: &[u8]) -> Vec<u8> {
+    let mut out = twox_64(data).to_vec();
+    out.extend_from_slice(data);
+    out
+}
+
+/// Minimal SCALE-encoding helper so callers don't need to pull in the `Encode` trait
+/// just to hash a block number.
+trait EncodeScale {
+    fn encode_scale(&self) -> Vec<u8>;
+}
+
+impl EncodeScale for crate::BlockNumber {
+    fn encode_scale(&self) -> Vec<u8> {
+        codec::Encode::encode(self)
+    }
+}
+
+/// Reads the value stored under `key` at `block_hash` out of the `state_getReadProof` response,
+/// verifying the proof against the block's state root, and returns the raw SCALE-encoded value
+/// only if the proof checks out.
+///
+/// This mirrors what a light client does: the RPC node is treated as untrusted and the value
+/// is only accepted once its Merkle path to `state_root` has been walked and every node hash
+/// along the way matches - and, crucially, once `state_root` itself is proven to belong to
+/// `block_hash` rather than just taken on the same RPC node's word. Without that second check a
+/// malicious node could fabricate a header with a forged `state_root` plus a self-consistent
+/// proof against it; the trie walk alone only proves internal consistency between the proof and
+/// whatever root it's handed, not that the root is the real state at `block_hash`.
+pub async fn verify_storage(api: &Api, key: &[u8], block_hash: BlockHash) -> Result<Option<Vec<u8>>> {
+    let rpc = api.client.rpc();
+
+    let header = rpc
+        .header(Some(block_hash))
+        .await
+        .map_err(SubspaceCliError::Rpc)?
+        .ok_or_else(|| anyhow!("Header not found for block hash {}", block_hash))?;
+
+    let header_hash = BlockHash::from(blake2_256(&header.encode()));
+    if header_hash != block_hash {
+        return Err(anyhow!(
+            "Header returned for block hash {} actually hashes to {} - refusing to trust its state root",
+            block_hash, header_hash,
+        ));
+    }
+
+    let read_proof = rpc
+        .read_proof(vec![subxt::sp_core::storage::StorageKey(key.to_vec())], Some(block_hash))
+        .await
+        .map_err(SubspaceCliError::Rpc)?;
+
+    let db: ProofDb = read_proof
+        .proof
+        .into_iter()
+        .map(|node| (blake2_256(&node.0), node.0))
+        .collect();
+
+    verify_trie_proof(&db, header.state_root.as_bytes(), key)
+}
+
+/// Walks the base-16 hexary Patricia-Merkle trie described by `db`, starting from the node
+/// whose blake2-256 hash is `root`, following the nibbles of `key` until a leaf or an empty
+/// branch slot is reached.
+///
+/// At every step the child referenced by a branch node is either looked up by hash in `db`
+/// (trusted only because `db` is keyed by each node's own blake2-256 hash, so a lookup can only
+/// succeed for a node that actually hashes to the parent's pointer) or, for small subtries,
+/// taken inline from the parent itself. A branch's own partial key is consumed and checked
+/// against the remaining nibbles before its selector nibble is - dropping it would desync every
+/// proof whose branch nodes share a prefix, which in practice is every proof (they all share the
+/// 64-nibble `twox_128(pallet)+twox_128(item)` prefix at the top of the trie).
+fn verify_trie_proof(db: &ProofDb, root: &[u8], key: &[u8]) -> Result<Option<Vec<u8>>> {
+    let nibbles = bytes_to_nibbles(key);
+    let root_hash: [u8; 32] = root
+        .try_into()
+        .map_err(|_| anyhow!("State root is not 32 bytes"))?;
+    let mut current = resolve_hash(db, &root_hash)?;
+    let mut offset = 0usize;
+
+    loop {
+        match current {
+            TrieNode::Empty => return Ok(None),
+            TrieNode::Leaf { partial, value } => {
+                return if nibbles[offset..] == partial[..] {
+                    Ok(Some(value))
+                } else {
+                    Ok(None)
+                };
+            }
+            TrieNode::Branch {
+                partial,
+                children,
+                value,
+            } => {
+                if !nibbles[offset..].starts_with(partial.as_slice()) {
+                    return Ok(None);
+                }
+                offset += partial.len();
+
+                if offset == nibbles.len() {
+                    return Ok(value);
+                }
+
+                let index = nibbles[offset] as usize;
+                offset += 1;
+                match &children[index] {
+                    Some(child) => current = resolve_child(db, child)?,
+                    None => return Ok(None),
+                }
+            }
+        }
+    }
+}
+
+/// A branch child pointer: either a reference to another proof node by its blake2-256 hash, or
+/// (for small subtries) the child node's encoding inlined directly in the parent.
+enum ChildRef {
+    Hash([u8; 32]),
+    Inline(Vec<u8>),
+}
+
+fn resolve_hash(db: &ProofDb, hash: &[u8; 32]) -> Result<TrieNode> {
+    let node = db
+        .get(hash)
+        .ok_or_else(|| anyhow!("Proof is missing node for hash {}", hex::encode(hash)))?;
+    TrieNode::decode(node)
+}
+
+fn resolve_child(db: &ProofDb, child: &ChildRef) -> Result<TrieNode> {
+    match child {
+        ChildRef::Hash(hash) => resolve_hash(db, hash),
+        ChildRef::Inline(node) => TrieNode::decode(node),
+    }
+}
+
+enum TrieNode {
+    Empty,
+    Leaf {
+        partial: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Branch {
+        partial: Vec<u8>,
+        children: [Option<ChildRef>; 16],
+        value: Option<Vec<u8>>,
+    },
+}
+
+impl TrieNode {
+    /// Decodes one of Substrate's trie node encodings (empty / leaf / branch). This only
+    /// supports the shapes that `state_getReadProof` ever hands back for a single-key proof;
+    /// anything else is rejected.
+    fn decode(mut node: &[u8]) -> Result<Self> {
+        if node.is_empty() {
+            return Ok(TrieNode::Empty);
+        }
+
+        let header = node[0];
+        node = &node[1..];
+
+        match header >> 6 {
+            // Leaf: high bits `01`.
+            0b01 => {
+                let partial = decode_partial_key(header & 0b0011_1111, &mut node)?;
+                let value = decode_length_prefixed(&mut node)?;
+                Ok(TrieNode::Leaf { partial, value })
+            }
+            // Branch: high bits `10` (no value) or `11` (with value).
+            0b10 | 0b11 => {
+                let has_value = header >> 6 == 0b11;
+                let partial = decode_partial_key(header & 0b0011_1111, &mut node)?;
+
+                let bitmap = u16::decode(&mut node)?;
+                let value = if has_value {
+                    Some(decode_length_prefixed(&mut node)?)
+                } else {
+                    None
+                };
+
+                let mut children: [Option<ChildRef>; 16] = Default::default();
+                for (index, slot) in children.iter_mut().enumerate() {
+                    if bitmap & (1 << index) != 0 {
+                        let child = decode_length_prefixed(&mut node)?;
+                        *slot = Some(match <[u8; 32]>::try_from(child.as_slice()) {
+                            Ok(hash) => ChildRef::Hash(hash),
+                            // Shorter than a hash: the child node is small enough to be
+                            // embedded directly rather than referenced.
+                            Err(_) => ChildRef::Inline(child),
+                        });
+                    }
+                }
+
+                Ok(TrieNode::Branch {
+                    partial,
+                    children,
+                    value,
+                })
+            }
+            _ => Err(anyhow!("Unsupported trie node header {:#x}", header)),
+        }
+    }
+}
+
+/// Extension-byte constant: when the inline 6-bit length field is maxed out, the real length is
+/// `63 + sum of continuation bytes` (each continuation byte of `255` means "keep reading").
+const PARTIAL_LEN_INLINE_MAX: u8 = 0b0011_1111;
+
+fn decode_partial_len(first_byte_len: u8, node: &mut &[u8]) -> Result<usize> {
+    let mut len = first_byte_len as usize;
+    if first_byte_len == PARTIAL_LEN_INLINE_MAX {
+        loop {
+            let (byte, rest) = node
+                .split_first()
+                .ok_or_else(|| anyhow!("Truncated partial-key length in trie node"))?;
+            *node = rest;
+            len += *byte as usize;
+            if *byte < 255 {
+                break;
+            }
+        }
+    }
+    Ok(len)
+}
+
+fn decode_partial_key(first_byte_len: u8, node: &mut &[u8]) -> Result<Vec<u8>> {
+    let len = decode_partial_len(first_byte_len, node)?;
+    let nibble_bytes = (len + 1) / 2;
+    if node.len() < nibble_bytes {
+        return Err(anyhow!("Truncated partial key in trie node"));
+    }
+    let (key_bytes, rest) = node.split_at(nibble_bytes);
+    *node = rest;
+
+    let mut nibbles = bytes_to_nibbles(key_bytes);
+    if len % 2 == 1 {
+        nibbles.remove(0);
+    }
+    nibbles.truncate(len);
+    Ok(nibbles)
+}
+
+fn decode_length_prefixed(node: &mut &[u8]) -> Result<Vec<u8>> {
+    let len = codec::Compact::<u32>::decode(node)?.0 as usize;
+    if node.len() < len {
+        return Err(anyhow!("Truncated value in trie node"));
+    }
+    let (value, rest) = node.split_at(len);
+    *node = rest;
+    Ok(value.to_vec())
+}
+
+fn bytes_to_nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0x0f])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use codec::Encode;
+
+    use super::*;
+
+    /// Builds a two-node trie - a branch with partial key `[0xA, 0xB, 0xC]` and a single inline
+    /// leaf child at nibble `0xD` holding `value` - and returns `(db, root_hash)`. This exercises
+    /// the branch-partial-key skip and inline-child decoding in the same shape every real
+    /// `System::Account`/`System::BlockHash` proof has: a shared pallet+item prefix followed by
+    /// a small subtrie.
+    fn branch_with_inline_leaf(value: &[u8]) -> (ProofDb, [u8; 32]) {
+        // Leaf: header (no partial), Compact-length-prefixed value.
+        let mut leaf = vec![0b0100_0000u8];
+        leaf.extend(codec::Compact(value.len() as u32).encode());
+        leaf.extend_from_slice(value);
+        assert!(leaf.len() < 32, "test fixture must stay inline");
+
+        // Branch: header (partial len 3, no value), partial key bytes, child bitmap (bit 13
+        // set), then the leaf inlined behind its own Compact length prefix.
+        let mut root = vec![0b1000_0000u8 | 3];
+        root.extend_from_slice(&[0x0A, 0xBC]); // nibbles [pad, 0xA, 0xB, 0xC] -> partial [A,B,C]
+        root.extend_from_slice(&(1u16 << 13).to_le_bytes());
+        root.extend(codec::Compact(leaf.len() as u32).encode());
+        root.extend_from_slice(&leaf);
+
+        let root_hash = blake2_256(&root);
+        let mut db = ProofDb::new();
+        db.insert(root_hash, root);
+        (db, root_hash)
+    }
+
+    #[test]
+    fn verifies_value_behind_branch_partial_key_and_inline_child() {
+        let (db, root_hash) = branch_with_inline_leaf(b"hello");
+        // Nibbles [0xA, 0xB, 0xC, 0xD]: branch partial consumes [A,B,C], selector nibble is D.
+        let key = [0xABu8, 0xCD];
+
+        let value = verify_trie_proof(&db, &root_hash, &key).unwrap();
+        assert_eq!(value, Some(b"hello".to_vec()));
+    }
+
+    #[test]
+    fn rejects_tampered_root_node() {
+        let (db, root_hash) = branch_with_inline_leaf(b"hello");
+        let key = [0xABu8, 0xCD];
+
+        // A node whose bytes were tampered with no longer hashes to `root_hash`, so it simply
+        // won't be found in a `db` keyed by each node's own hash - this is the proof mechanism.
+        let mut tampered_db = ProofDb::new();
+        let mut tampered_root = db.get(&root_hash).unwrap().clone();
+        *tampered_root.last_mut().unwrap() ^= 0xFF;
+        tampered_db.insert(blake2_256(&tampered_root), tampered_root);
+
+        assert!(verify_trie_proof(&tampered_db, &root_hash, &key).is_err());
+    }
+
+    #[test]
+    fn decodes_extended_partial_key_length() {
+        // First-byte field maxed out at 63, plus one continuation byte of 2 -> length 65.
+        let mut node: &[u8] = &[2];
+        let len = decode_partial_len(PARTIAL_LEN_INLINE_MAX, &mut node).unwrap();
+        assert_eq!(len, 65);
+        assert!(node.is_empty());
+    }
+}
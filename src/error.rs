@@ -0,0 +1,20 @@
+use thiserror::Error;
+
+use crate::BlockNumber;
+
+/// Errors produced by the Subspace CLI itself, as opposed to being propagated verbatim from
+/// the underlying RPC/codec libraries.
+#[derive(Debug, Error)]
+pub enum SubspaceCliError {
+    #[error("Block hash for block number {number} not found")]
+    BlockHashNotFound { number: BlockNumber },
+
+    #[error("Best block hash not found")]
+    BestBlockUnavailable,
+
+    #[error("Failed to decode storage data: {0}")]
+    StorageKeyDecode(#[from] codec::Error),
+
+    #[error("RPC request failed: {0}")]
+    Rpc(#[from] subxt::Error),
+}
@@ -1,4 +1,6 @@
 mod commands;
+mod error;
+mod output;
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
@@ -8,6 +10,10 @@ use subxt::{
     ClientBuilder, DefaultConfig, SubstrateExtrinsicParams,
 };
 
+use commands::snapshot::SnapshotFormat;
+use error::SubspaceCliError;
+use output::{AccountRow, BlockHashRow, EventRow, OutputFormat};
+
 #[subxt::subxt(runtime_metadata_path = "subspace_metadata.scale")]
 mod subspace {}
 
@@ -33,6 +39,16 @@ struct SubspaceCli {
     #[clap(long)]
     pub block_hash: Option<BlockHash>,
 
+    /// Verify the RPC node's response against the block's state root using a Merkle
+    /// storage proof instead of trusting it outright. Only `system account` and
+    /// `system block-hash` honour it; the other commands ignore it.
+    #[clap(long)]
+    pub verify: bool,
+
+    /// How to render the command's output.
+    #[clap(long, arg_enum, default_value = "pretty")]
+    pub output: OutputFormat,
+
     #[clap(subcommand)]
     command: Commands,
 }
@@ -40,10 +56,24 @@ struct SubspaceCli {
 #[derive(Debug, Subcommand)]
 enum Commands {
     /// Snapshot the state for the regenesis purpose.
-    Snapshot,
+    Snapshot {
+        /// Emit a `state` dump (default) or a genesis `chainspec` patch of free balances.
+        #[clap(long, arg_enum, default_value = "state")]
+        format: SnapshotFormat,
+        /// Where to write the chain spec when `--format chainspec` is used.
+        #[clap(long)]
+        out: Option<std::path::PathBuf>,
+    },
     /// System.
     #[clap(subcommand)]
     System(SystemCommands),
+    /// Inspect the node's live runtime version against the metadata this binary was built with.
+    Metadata {
+        /// Load an alternate `.scale` metadata file at runtime instead of the one baked into
+        /// the binary. Only affects this command's own output.
+        #[clap(long)]
+        path: Option<std::path::PathBuf>,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -57,16 +87,30 @@ enum SystemCommands {
     Events,
     /// `BlockHash` mapping.
     BlockHash,
+    /// Historical balance and total issuance over a block range.
+    Balance {
+        #[clap(long, parse(try_from_str))]
+        who: AccountId,
+        /// First block number in the range, inclusive.
+        #[clap(long)]
+        from: BlockNumber,
+        /// Last block number in the range, inclusive.
+        #[clap(long)]
+        to: BlockNumber,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = SubspaceCli::parse();
+    let verify = cli.verify;
+    let output = cli.output;
 
     let api = ClientBuilder::new()
         .set_url(cli.url)
         .build()
-        .await?
+        .await
+        .map_err(SubspaceCliError::Rpc)?
         .to_runtime_api::<Api>();
 
     let maybe_block_hash = if let Some(block_number) = cli.block_number {
@@ -74,10 +118,11 @@ async fn main() -> Result<()> {
             api.client
                 .rpc()
                 .block_hash(Some(block_number.into()))
-                .await?
-                .unwrap_or_else(|| {
-                    panic!("Block hash for block number {} not found", block_number)
-                }),
+                .await
+                .map_err(SubspaceCliError::Rpc)?
+                .ok_or(SubspaceCliError::BlockHashNotFound {
+                    number: block_number,
+                })?,
         )
     } else {
         cli.block_hash
@@ -89,20 +134,59 @@ async fn main() -> Result<()> {
             .client
             .rpc()
             .block_hash(None)
-            .await?
-            .expect("Best block hash not found"),
+            .await
+            .map_err(SubspaceCliError::Rpc)?
+            .ok_or(SubspaceCliError::BestBlockUnavailable)?,
     };
 
+    commands::metadata::check_spec_version(&api, block_hash).await?;
+
     match cli.command {
-        Commands::Snapshot => commands::snapshot::snapshot(&api, block_hash).await?,
+        Commands::Snapshot { format, out } => {
+            commands::snapshot::snapshot(&api, block_hash, output, format, out).await?
+        }
+        Commands::Metadata { path } => commands::metadata::metadata(&api, block_hash, path).await?,
         Commands::System(system_commands) => match system_commands {
             SystemCommands::Account { who } => {
-                let account = api.storage().system().account(&who, Some(block_hash)).await;
-                println!("{:#?}", account);
+                let account = if verify {
+                    let key = commands::verify::system_account_key(&who);
+                    let raw = commands::verify::verify_storage(&api, &key, block_hash)
+                        .await?
+                        .ok_or_else(|| anyhow::anyhow!("Account {} not found", who))?;
+                    Decode::decode(&mut raw.as_slice()).map_err(SubspaceCliError::StorageKeyDecode)?
+                } else {
+                    api.storage()
+                        .system()
+                        .account(&who, Some(block_hash))
+                        .await
+                        .map_err(SubspaceCliError::Rpc)?
+                };
+
+                let row = AccountRow {
+                    who: who.to_string(),
+                    nonce: account.nonce,
+                    free: account.data.free,
+                    reserved: account.data.reserved,
+                    misc_frozen: account.data.misc_frozen,
+                    fee_frozen: account.data.fee_frozen,
+                };
+                output::print_rows(&[row], output)?;
             }
             SystemCommands::Events => {
-                let events = api.storage().system().events(Some(block_hash)).await;
-                println!("{:#?}", events);
+                let events = api
+                    .storage()
+                    .system()
+                    .events(Some(block_hash))
+                    .await
+                    .map_err(SubspaceCliError::Rpc)?;
+                let rows: Vec<EventRow> = events
+                    .into_iter()
+                    .map(|record| EventRow {
+                        phase: format!("{:?}", record.phase),
+                        event: format!("{:?}", record.event),
+                    })
+                    .collect();
+                output::print_rows(&rows, output)?;
             }
             SystemCommands::BlockHash => {
                 const TWOX_HASH_LEN: usize = 16; // 8 bytes hex
@@ -112,15 +196,40 @@ async fn main() -> Result<()> {
                     .storage()
                     .system()
                     .block_hash_iter(Some(block_hash))
-                    .await?;
+                    .await
+                    .map_err(SubspaceCliError::Rpc)?;
 
-                while let Some((key, hash)) = iter.next().await? {
+                let mut rows = Vec::new();
+                while let Some((key, hash)) = iter.next().await.map_err(SubspaceCliError::Rpc)? {
                     let key = hex::encode(&key.0);
                     let number: u32 = Decode::decode(
                         &mut hex::decode(&key[STORAGE_PREFIX_LEN + TWOX_HASH_LEN..])?.as_slice(),
-                    )?;
-                    println!("{}: {}", number, hash);
+                    )
+                    .map_err(SubspaceCliError::StorageKeyDecode)?;
+
+                    let hash = if verify {
+                        let storage_key = commands::verify::system_block_hash_key(number);
+                        let raw =
+                            commands::verify::verify_storage(&api, &storage_key, block_hash)
+                                .await?
+                                .ok_or_else(|| {
+                                    anyhow::anyhow!("No proof for block number {}", number)
+                                })?;
+                        Decode::decode(&mut raw.as_slice())
+                            .map_err(SubspaceCliError::StorageKeyDecode)?
+                    } else {
+                        hash
+                    };
+
+                    rows.push(BlockHashRow {
+                        number,
+                        hash: hash.to_string(),
+                    });
                 }
+                output::print_rows(&rows, output)?;
+            }
+            SystemCommands::Balance { who, from, to } => {
+                commands::history::history(&api, who, from, to, output).await?
             }
         },
     }
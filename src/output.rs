@@ -0,0 +1,80 @@
+use anyhow::Result;
+use serde::Serialize;
+
+/// How a command's result should be rendered.
+#[derive(Copy, Clone, Debug, clap::ArgEnum)]
+pub enum OutputFormat {
+    /// Rust debug output, human-oriented (the default).
+    Pretty,
+    /// One JSON array of rows.
+    Json,
+    /// Comma-separated rows with a header, for spreadsheets and `awk`.
+    Csv,
+}
+
+impl Default for OutputFormat {
+    fn default() -> Self {
+        OutputFormat::Pretty
+    }
+}
+
+/// A `System::Account` entry, flattened for machine-readable output.
+#[derive(Debug, Serialize)]
+pub struct AccountRow {
+    pub who: String,
+    pub nonce: u32,
+    pub free: u128,
+    pub reserved: u128,
+    pub misc_frozen: u128,
+    pub fee_frozen: u128,
+}
+
+/// A single `System::Events` record, flattened for machine-readable output.
+#[derive(Debug, Serialize)]
+pub struct EventRow {
+    pub phase: String,
+    pub event: String,
+}
+
+/// One entry of the `System::BlockHash` map.
+#[derive(Debug, Serialize)]
+pub struct BlockHashRow {
+    pub number: u32,
+    pub hash: String,
+}
+
+/// An account's balance and the chain's total issuance at a single block, one row of a
+/// `System Balance` history range.
+#[derive(Debug, Serialize)]
+pub struct BalanceHistoryRow {
+    pub number: u32,
+    pub free: u128,
+    pub reserved: u128,
+    pub issuance: u128,
+}
+
+/// Prints `rows` in `format`, falling back to `{:#?}` debug output in `Pretty` mode.
+pub fn print_rows<T>(rows: &[T], format: OutputFormat) -> Result<()>
+where
+    T: Serialize + std::fmt::Debug,
+{
+    match format {
+        OutputFormat::Pretty => {
+            for row in rows {
+                println!("{:#?}", row);
+            }
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(rows)?);
+        }
+        OutputFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(std::io::stdout());
+            for row in rows {
+                writer.serialize(row)?;
+            }
+            writer.flush()?;
+        }
+    }
+
+    Ok(())
+}